@@ -0,0 +1,191 @@
+//! Specialized, performance-oriented digit-writing algorithms.
+
+#![doc(hidden)]
+
+use core::ptr;
+
+use lexical_util::digit::digit_to_char;
+use lexical_util::format::NumberFormat;
+use lexical_util::num::UnsignedInteger;
+
+/// Write the digits of `value` into `buffer`, using the radix from `FORMAT`.
+///
+/// Writes least-significant-digit first into the tail of `buffer`, then
+/// shifts the written digits to the front, returning the number of bytes
+/// written.
+///
+/// # Safety
+///
+/// Safe as long as `buffer` is large enough to hold the formatted digits
+/// of `value` in the given radix.
+#[inline]
+pub(crate) unsafe fn algorithm<T: UnsignedInteger, const FORMAT: u128>(
+    mut value: T,
+    buffer: &mut [u8],
+) -> usize {
+    let format = NumberFormat::<FORMAT> {};
+    let radix = T::from_u32(format.radix());
+
+    let mut index = buffer.len();
+    loop {
+        index -= 1;
+        let digit = (value % radix).as_u32();
+        // SAFETY: `index` starts at `buffer.len()` and only decreases, and
+        // the caller guarantees `buffer` is large enough for all of `value`'s
+        // digits, so `index` never underflows past `0`.
+        unsafe {
+            index_unchecked_mut!(buffer[index]) = digit_to_char(digit);
+        }
+        value /= radix;
+        if value == T::ZERO {
+            break;
+        }
+    }
+
+    let count = buffer.len() - index;
+    if index != 0 {
+        // SAFETY: `buffer[index..]` and `buffer[..count]` are both valid,
+        // non-overlapping ranges of `count` bytes within `buffer`.
+        unsafe {
+            ptr::copy(buffer.as_ptr().add(index), buffer.as_mut_ptr(), count);
+        }
+    }
+    count
+}
+
+/// Write `value` into `buffer`, zero-padded on the left to exactly `width`
+/// digits.
+///
+/// # Safety
+///
+/// Safe as long as `buffer` is at least `width` bytes, and `value` fits in
+/// `width` digits of the given radix.
+#[inline]
+unsafe fn write_padded_chunk<const FORMAT: u128>(value: u64, width: usize, buffer: &mut [u8]) -> usize {
+    // SAFETY: `buffer` is at least `width` bytes by the caller's contract,
+    // and `algorithm` writes at most `width` digits since `value` fits in
+    // `width` digits of the given radix.
+    let count = unsafe { algorithm::<u64, FORMAT>(value, buffer) };
+    if count < width {
+        // SAFETY: shifts the `count` written digits to the end of the
+        // `width`-byte region and fills the gap with zeros, all within
+        // the caller-guaranteed `buffer[..width]`.
+        unsafe {
+            ptr::copy(buffer.as_ptr(), buffer.as_mut_ptr().add(width - count), count);
+            ptr::write_bytes(buffer.as_mut_ptr(), b'0', width - count);
+        }
+    }
+    width
+}
+
+/// Largest power of ten that fits in a `u64` (`10^19`), and its exponent.
+const U64_POW10: u64 = 10_000_000_000_000_000_000;
+const U64_POW10_EXP: usize = 19;
+
+/// Largest `radix^k` that fits in a `u64`, and `k`.
+#[inline]
+fn u64_step(radix: u128) -> (u128, usize) {
+    if radix == 10 {
+        return (U64_POW10 as u128, U64_POW10_EXP);
+    }
+
+    let mut step: u128 = 1;
+    let mut width = 0usize;
+    while step.wrapping_mul(radix) <= u64::MAX as u128 {
+        step *= radix;
+        width += 1;
+    }
+    (step, width)
+}
+
+/// Format a `u128` by splitting it into at most three `u64` chunks.
+///
+/// 128-bit division is dramatically slower than 64-bit division on all
+/// common targets. Repeatedly taking `value % step` and `value / step`,
+/// where `step` is the largest power of the radix that fits in a `u64`,
+/// keeps the hot division loop entirely in 64-bit arithmetic: the
+/// most-significant chunk is written normally and every lower chunk is
+/// zero-padded to exactly the chunk width, mirroring the chunked scheme
+/// libcore uses for `u128`'s `Display` implementation.
+///
+/// # Safety
+///
+/// Safe as long as `buffer` is large enough to hold the formatted digits
+/// of `value` in the given radix.
+#[inline]
+pub(crate) unsafe fn algorithm_128bit<const FORMAT: u128>(value: u128, buffer: &mut [u8]) -> usize {
+    let format = NumberFormat::<FORMAT> {};
+    let radix = format.radix() as u128;
+    let (step, width) = u64_step(radix);
+
+    if value <= u64::MAX as u128 {
+        // SAFETY: `buffer` is large enough for `value`'s digits, guaranteed
+        // by the caller.
+        return unsafe { algorithm::<u64, FORMAT>(value as u64, buffer) };
+    }
+
+    let low = (value % step) as u64;
+    let high = value / step;
+
+    if high <= u64::MAX as u128 {
+        // SAFETY: `buffer` holds `high`'s digits followed by `width`
+        // padded digits for `low`, guaranteed by the caller.
+        unsafe {
+            let high_count = algorithm::<u64, FORMAT>(high as u64, buffer);
+            let low_buffer = &mut index_unchecked_mut!(buffer[high_count..]);
+            write_padded_chunk::<FORMAT>(low, width, low_buffer);
+            return high_count + width;
+        }
+    }
+
+    // `value` is large enough to need all three chunks.
+    let mid = (high % step) as u64;
+    let top = (high / step) as u64;
+
+    // SAFETY: `buffer` holds `top`'s digits followed by `width` padded
+    // digits each for `mid` and `low`, guaranteed by the caller.
+    unsafe {
+        let top_count = algorithm::<u64, FORMAT>(top, buffer);
+        let mid_buffer = &mut index_unchecked_mut!(buffer[top_count..]);
+        write_padded_chunk::<FORMAT>(mid, width, mid_buffer);
+        let low_buffer = &mut index_unchecked_mut!(buffer[top_count + width..]);
+        write_padded_chunk::<FORMAT>(low, width, low_buffer);
+        top_count + 2 * width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lexical_util::format::STANDARD;
+
+    fn format_128bit(value: u128) -> std::string::String {
+        let mut buffer = [0u8; 40];
+        // SAFETY: 40 bytes is large enough for any u128's decimal digits.
+        let count = unsafe { algorithm_128bit::<{ STANDARD }>(value, &mut buffer) };
+        std::str::from_utf8(&buffer[..count]).unwrap().into()
+    }
+
+    #[test]
+    fn single_chunk() {
+        assert_eq!(format_128bit(12345), "12345");
+        assert_eq!(format_128bit(u64::MAX as u128), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn two_chunks() {
+        // One past u64::MAX: the smallest value needing a second chunk.
+        let value = u64::MAX as u128 + 1;
+        assert_eq!(format_128bit(value), value.to_string());
+
+        // Exercises the low chunk's zero-padding: low digits are all zero.
+        let value = (U64_POW10 as u128) * 3;
+        assert_eq!(format_128bit(value), value.to_string());
+    }
+
+    #[test]
+    fn three_chunks() {
+        assert_eq!(format_128bit(u128::MAX), u128::MAX.to_string());
+        assert_eq!(format_128bit(0), "0");
+    }
+}
@@ -2,6 +2,8 @@
 
 #![doc(hidden)]
 
+use core::ptr;
+
 use crate::options::Options;
 use crate::write::WriteInteger;
 use lexical_util::assert::{assert_buffer, debug_assert_buffer};
@@ -10,6 +12,27 @@ use lexical_util::format::{NumberFormat, STANDARD};
 use lexical_util::num::SignedInteger;
 use lexical_util::{to_lexical, to_lexical_with_options};
 
+/// Zero-pad the digits written to `buffer[sign_len..sign_len + digits_len]`
+/// up to `min_digits`, returning the new total length (including the sign).
+///
+/// # Safety
+///
+/// Safe as long as `buffer` can hold at least `sign_len + min_digits` elements.
+#[inline]
+unsafe fn pad_mantissa(buffer: &mut [u8], sign_len: usize, digits_len: usize, min_digits: usize) -> usize {
+    if digits_len >= min_digits {
+        return sign_len + digits_len;
+    }
+    let pad = min_digits - digits_len;
+    // SAFETY: safe as long as `buffer` can hold `sign_len + min_digits` elements.
+    unsafe {
+        let digits = index_unchecked_mut!(buffer[sign_len..sign_len + digits_len]).as_mut_ptr();
+        ptr::copy(digits, digits.add(pad), digits_len);
+        ptr::write_bytes(buffer.as_mut_ptr().add(sign_len), b'0', pad);
+    }
+    sign_len + min_digits
+}
+
 // UNSIGNED
 
 /// Callback for unsigned integer formatter.
@@ -17,21 +40,28 @@ use lexical_util::{to_lexical, to_lexical_with_options};
 /// # Safety
 ///
 /// Safe as long as the buffer can hold `FORMATTED_SIZE` elements
-/// (or `FORMATTED_SIZE_DECIMAL` for decimal).
+/// (or `FORMATTED_SIZE_DECIMAL` for decimal), or `min_digits` plus a sign,
+/// whichever is larger.
 #[inline]
-unsafe fn unsigned<T: WriteInteger, const FORMAT: u128>(value: T, buffer: &mut [u8]) -> usize {
+unsafe fn unsigned<T: WriteInteger, const FORMAT: u128>(
+    value: T,
+    buffer: &mut [u8],
+    options: &Options,
+) -> usize {
     let format = NumberFormat::<FORMAT> {};
-    if cfg!(feature = "format") && format.required_mantissa_sign() {
+    let (sign_len, digits_len) = if cfg!(feature = "format") && format.required_mantissa_sign() {
         // SAFETY: safe as long as there is at least `FORMATTED_SIZE` elements.
         unsafe {
             index_unchecked_mut!(buffer[0]) = b'+';
             let buffer = &mut index_unchecked_mut!(buffer[1..]);
-            value.write_mantissa::<FORMAT>(buffer) + 1
+            (1, value.write_mantissa::<FORMAT>(buffer))
         }
     } else {
         // SAFETY: safe as long as there is at least `FORMATTED_SIZE` elements.
-        unsafe { value.write_mantissa::<FORMAT>(buffer) }
-    }
+        (0, unsafe { value.write_mantissa::<FORMAT>(buffer) })
+    };
+    // SAFETY: safe as long as the buffer can hold `sign_len + min_digits` elements.
+    unsafe { pad_mantissa(buffer, sign_len, digits_len, options.min_digits()) }
 }
 
 // SIGNED
@@ -41,32 +71,39 @@ unsafe fn unsigned<T: WriteInteger, const FORMAT: u128>(value: T, buffer: &mut [
 /// # Safety
 ///
 /// Safe as long as the buffer can hold `FORMATTED_SIZE` elements
-/// (or `FORMATTED_SIZE_DECIMAL` for decimal).
+/// (or `FORMATTED_SIZE_DECIMAL` for decimal), or `min_digits` plus a sign,
+/// whichever is larger.
 #[inline]
-unsafe fn signed<T: SignedInteger, const FORMAT: u128>(value: T, buffer: &mut [u8]) -> usize
+unsafe fn signed<T: SignedInteger, const FORMAT: u128>(
+    value: T,
+    buffer: &mut [u8],
+    options: &Options,
+) -> usize
 where
     T::Unsigned: WriteInteger,
 {
     let format = NumberFormat::<FORMAT> {};
     let unsigned = value.unsigned_abs();
-    if value < T::ZERO {
+    let (sign_len, digits_len) = if value < T::ZERO {
         // SAFETY: safe as long as there is at least `FORMATTED_SIZE` elements.
         unsafe {
             index_unchecked_mut!(buffer[0]) = b'-';
             let buffer = &mut index_unchecked_mut!(buffer[1..]);
-            unsigned.write_mantissa::<FORMAT>(buffer) + 1
+            (1, unsigned.write_mantissa::<FORMAT>(buffer))
         }
     } else if cfg!(feature = "format") && format.required_mantissa_sign() {
         // SAFETY: safe as long as there is at least `FORMATTED_SIZE` elements.
         unsafe {
             index_unchecked_mut!(buffer[0]) = b'+';
             let buffer = &mut index_unchecked_mut!(buffer[1..]);
-            unsigned.write_mantissa::<FORMAT>(buffer) + 1
+            (1, unsigned.write_mantissa::<FORMAT>(buffer))
         }
     } else {
         // SAFETY: safe as long as there is at least `FORMATTED_SIZE` elements.
-        unsafe { unsigned.write_mantissa::<FORMAT>(buffer) }
-    }
+        (0, unsafe { unsigned.write_mantissa::<FORMAT>(buffer) })
+    };
+    // SAFETY: safe as long as the buffer can hold `sign_len + min_digits` elements.
+    unsafe { pad_mantissa(buffer, sign_len, digits_len, options.min_digits()) }
 }
 
 // API
@@ -79,7 +116,7 @@ impl<T: WriteInteger + FormattedSize> ToLexical for T {
         debug_assert_buffer::<T>(10, bytes.len());
         // SAFETY: safe if `bytes.len() > Self::FORMATTED_SIZE_DECIMAL`.
         unsafe {
-            let len = unsigned::<T, { STANDARD }>(self, bytes);
+            let len = unsigned::<T, { STANDARD }>(self, bytes, &Options::new());
             &mut index_unchecked_mut!(bytes[..len])
         }
     }
@@ -97,13 +134,14 @@ impl<T: WriteInteger + FormattedSize> ToLexicalWithOptions for T {
     unsafe fn to_lexical_with_options_unchecked<'a, const FORMAT: u128>(
         self,
         bytes: &'a mut [u8],
-        _: &Self::Options,
+        options: &Self::Options,
     ) -> &'a mut [u8] {
         debug_assert_buffer::<T>(NumberFormat::<{ FORMAT }>::RADIX, bytes.len());
+        debug_assert!(bytes.len() >= options.min_digits() + 1);
         assert!(NumberFormat::<{ FORMAT }> {}.is_valid());
-        // SAFETY: safe if `bytes.len() > Self::FORMATTED_SIZE`.
+        // SAFETY: safe if `bytes.len() > Self::FORMATTED_SIZE`, or `min_digits` plus a sign.
         unsafe {
-            let len = unsigned::<T, FORMAT>(self, bytes);
+            let len = unsigned::<T, FORMAT>(self, bytes, options);
             &mut index_unchecked_mut!(bytes[..len])
         }
     }
@@ -114,8 +152,9 @@ impl<T: WriteInteger + FormattedSize> ToLexicalWithOptions for T {
         options: &Self::Options,
     ) -> &'a mut [u8] {
         assert_buffer::<T>(NumberFormat::<{ FORMAT }>::RADIX, bytes.len());
+        assert!(bytes.len() >= options.min_digits() + 1);
         assert!(NumberFormat::<{ FORMAT }> {}.is_valid());
-        // SAFETY: safe since `bytes.len() > Self::FORMATTED_SIZE`.
+        // SAFETY: safe since `bytes.len() > Self::FORMATTED_SIZE`, or `min_digits` plus a sign.
         unsafe { self.to_lexical_with_options_unchecked::<FORMAT>(bytes, options) }
     }
 }
@@ -128,7 +167,7 @@ macro_rules! signed_to_lexical {
                 debug_assert_buffer::<$t>(10, bytes.len());
                 // SAFETY: safe if `bytes.len() > Self::FORMATTED_SIZE_DECIMAL`.
                 unsafe {
-                    let len = signed::<$t, { STANDARD }>(self, bytes);
+                    let len = signed::<$t, { STANDARD }>(self, bytes, &Options::new());
                     &mut index_unchecked_mut!(bytes[..len])
                 }
             }
@@ -146,14 +185,15 @@ macro_rules! signed_to_lexical {
             unsafe fn to_lexical_with_options_unchecked<'a, const FORMAT: u128>(
                 self,
                 bytes: &'a mut [u8],
-                _: &Self::Options,
+                options: &Self::Options,
             ) -> &'a mut [u8]
             {
                 debug_assert_buffer::<$t>(NumberFormat::<{ FORMAT }>::RADIX, bytes.len());
+                debug_assert!(bytes.len() >= options.min_digits() + 1);
                 assert!(NumberFormat::<{ FORMAT }> {}.is_valid());
-                // SAFETY: safe if `bytes.len() > Self::FORMATTED_SIZE`.
+                // SAFETY: safe if `bytes.len() > Self::FORMATTED_SIZE`, or `min_digits` plus a sign.
                 unsafe {
-                    let len = signed::<$t, FORMAT>(self, bytes);
+                    let len = signed::<$t, FORMAT>(self, bytes, options);
                     &mut index_unchecked_mut!(bytes[..len])
                 }
             }
@@ -165,8 +205,9 @@ macro_rules! signed_to_lexical {
             ) -> &'a mut [u8]
             {
                 assert_buffer::<$t>(NumberFormat::<{ FORMAT }>::RADIX, bytes.len());
+                assert!(bytes.len() >= options.min_digits() + 1);
                 assert!(NumberFormat::<{ FORMAT }> {}.is_valid());
-                // SAFETY: safe since `bytes.len() > Self::FORMATTED_SIZE`.
+                // SAFETY: safe since `bytes.len() > Self::FORMATTED_SIZE`, or `min_digits` plus a sign.
                 unsafe { self.to_lexical_with_options_unchecked::<FORMAT>(bytes, options) }
             }
         }
@@ -174,3 +215,44 @@ macro_rules! signed_to_lexical {
 }
 
 signed_to_lexical! { i8 i16 i32 i64 i128 isize }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_mantissa_zero_pads_unsigned_digits() {
+        let mut buffer = *b"123\0\0";
+        // SAFETY: `buffer` holds `sign_len + min_digits` (5) bytes.
+        let len = unsafe { pad_mantissa(&mut buffer, 0, 3, 5) };
+        assert_eq!(len, 5);
+        assert_eq!(&buffer, b"00123");
+    }
+
+    #[test]
+    fn pad_mantissa_leaves_sign_in_place() {
+        let mut buffer = *b"-123\0\0";
+        // SAFETY: `buffer` holds `sign_len + min_digits` (1 + 5) bytes.
+        let len = unsafe { pad_mantissa(&mut buffer, 1, 3, 5) };
+        assert_eq!(len, 6);
+        assert_eq!(&buffer, b"-00123");
+    }
+
+    #[test]
+    fn pad_mantissa_is_noop_when_already_long_enough() {
+        let mut buffer = *b"12345";
+        // SAFETY: `buffer` holds `sign_len + digits_len` (5) bytes.
+        let len = unsafe { pad_mantissa(&mut buffer, 0, 5, 3) };
+        assert_eq!(len, 5);
+        assert_eq!(&buffer, b"12345");
+    }
+
+    #[test]
+    fn min_digits_option_pads_formatted_output() {
+        let mut options = Options::new();
+        options.set_min_digits(5);
+        let mut buffer = [0u8; 16];
+        let digits = 42u32.to_lexical_with_options::<{ STANDARD }>(&mut buffer, &options);
+        assert_eq!(core::str::from_utf8(digits).unwrap(), "00042");
+    }
+}
@@ -0,0 +1,38 @@
+//! Configuration options for writing integers to string.
+
+/// Options to customize integer-to-string formatting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Minimum number of digits to write, zero-padding shorter values.
+    ///
+    /// A sign character, if written, is not counted towards this width.
+    pub(crate) min_digits: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            min_digits: 0,
+        }
+    }
+}
+
+impl Options {
+    /// Create new options with default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the minimum number of digits to write.
+    #[inline]
+    pub fn min_digits(&self) -> usize {
+        self.min_digits
+    }
+
+    /// Set the minimum number of digits to write.
+    #[inline]
+    pub fn set_min_digits(&mut self, min_digits: usize) {
+        self.min_digits = min_digits;
+    }
+}
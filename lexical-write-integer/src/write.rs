@@ -0,0 +1,44 @@
+//! Trait to write the mantissa digits of an integer to a buffer.
+
+#![doc(hidden)]
+
+use lexical_util::num::UnsignedInteger;
+
+use crate::algorithm::{algorithm, algorithm_128bit};
+
+/// Write the significant digits of an integer to a buffer.
+pub trait WriteInteger: UnsignedInteger {
+    /// Write the digits for the integer to a buffer, returning the number
+    /// of bytes written.
+    ///
+    /// # Safety
+    ///
+    /// Safe as long as the buffer can hold `FORMATTED_SIZE` elements
+    /// (or `FORMATTED_SIZE_DECIMAL` for decimal).
+    unsafe fn write_mantissa<const FORMAT: u128>(self, buffer: &mut [u8]) -> usize;
+}
+
+macro_rules! write_integer_impl {
+    ($($t:ty)*) => ($(
+        impl WriteInteger for $t {
+            #[inline]
+            unsafe fn write_mantissa<const FORMAT: u128>(self, buffer: &mut [u8]) -> usize {
+                // SAFETY: safe if the buffer can hold `FORMATTED_SIZE` elements.
+                unsafe { algorithm::<Self, FORMAT>(self, buffer) }
+            }
+        }
+    )*)
+}
+
+write_integer_impl! { u8 u16 u32 u64 usize }
+
+// `u128` is formatted by splitting it into 64-bit chunks, since 128-bit
+// division is far slower than 64-bit division on all common targets.
+// See `algorithm_128bit` for details.
+impl WriteInteger for u128 {
+    #[inline]
+    unsafe fn write_mantissa<const FORMAT: u128>(self, buffer: &mut [u8]) -> usize {
+        // SAFETY: safe if the buffer can hold `FORMATTED_SIZE` elements.
+        unsafe { algorithm_128bit::<FORMAT>(self, buffer) }
+    }
+}
@@ -0,0 +1,91 @@
+//! Implements the algorithm in terms of the lexical API.
+
+#![doc(hidden)]
+
+use crate::options::Options;
+use lexical_util::assert::{assert_buffer, debug_assert_buffer};
+use lexical_util::{to_lexical, to_lexical_with_options};
+
+use super::grisu2::{
+    double_base10, float_base10, required_buffer_size, F32_MAX_EXPONENT_MAGNITUDE,
+    F64_MAX_EXPONENT_MAGNITUDE,
+};
+
+to_lexical! {}
+to_lexical_with_options! {}
+
+/// Implement `ToLexical`/`ToLexicalWithOptions` for a float type, forwarding
+/// to its `*_base10` callback.
+///
+/// `$max_exponent_magnitude` bounds how many leading/trailing zeros
+/// `$t`'s fixed-point notation can ever need (see
+/// `grisu2::F64_MAX_EXPONENT_MAGNITUDE`); `to_lexical_with_options` uses it
+/// to check `bytes` is large enough even when `options` requests more
+/// significant digits or forces fixed-point notation than `$t`'s default
+/// worst case (`FORMATTED_SIZE_DECIMAL`) accounts for.
+macro_rules! float_to_lexical {
+    ($t:ty, $cb:ident, $max_exponent_magnitude:expr) => {
+        impl ToLexical for $t {
+            unsafe fn to_lexical_unchecked(self, bytes: &mut [u8]) -> &mut [u8] {
+                debug_assert_buffer::<$t>(10, bytes.len());
+                // SAFETY: safe if `bytes.len() > Self::FORMATTED_SIZE_DECIMAL`.
+                unsafe {
+                    let first = bytes.as_mut_ptr();
+                    let last = $cb(self, first, &Options::new());
+                    let len = last.offset_from(first) as usize;
+                    &mut index_unchecked_mut!(bytes[..len])
+                }
+            }
+
+            fn to_lexical(self, bytes: &mut [u8]) -> &mut [u8] {
+                assert_buffer::<$t>(10, bytes.len());
+                // SAFETY: safe since `bytes.len() > Self::FORMATTED_SIZE_DECIMAL`.
+                unsafe { self.to_lexical_unchecked(bytes) }
+            }
+        }
+
+        impl ToLexicalWithOptions for $t {
+            type Options = Options;
+
+            unsafe fn to_lexical_with_options_unchecked<'a, const FORMAT: u128>(
+                self,
+                bytes: &'a mut [u8],
+                options: &Self::Options,
+            ) -> &'a mut [u8] {
+                debug_assert!(bytes.len() >= required_buffer_size(options, $max_exponent_magnitude));
+                // SAFETY: safe if `bytes.len() >= required_buffer_size(options, ..)`.
+                unsafe {
+                    let first = bytes.as_mut_ptr();
+                    let last = $cb(self, first, options);
+                    let len = last.offset_from(first) as usize;
+                    &mut index_unchecked_mut!(bytes[..len])
+                }
+            }
+
+            fn to_lexical_with_options<'a, const FORMAT: u128>(
+                self,
+                bytes: &'a mut [u8],
+                options: &Self::Options,
+            ) -> &'a mut [u8] {
+                assert!(bytes.len() >= required_buffer_size(options, $max_exponent_magnitude));
+                // SAFETY: safe since `bytes.len() >= required_buffer_size(options, ..)`.
+                unsafe { self.to_lexical_with_options_unchecked::<FORMAT>(bytes, options) }
+            }
+        }
+    };
+}
+
+float_to_lexical!(f32, float_base10, F32_MAX_EXPONENT_MAGNITUDE);
+float_to_lexical!(f64, double_base10, F64_MAX_EXPONENT_MAGNITUDE);
+
+cfg_if! {
+    if #[cfg(feature = "f16")] {
+        use half::{bf16, f16};
+        use super::grisu2::{
+            bf16_base10, f16_base10, BF16_MAX_EXPONENT_MAGNITUDE, F16_MAX_EXPONENT_MAGNITUDE,
+        };
+
+        float_to_lexical!(f16, f16_base10, F16_MAX_EXPONENT_MAGNITUDE);
+        float_to_lexical!(bf16, bf16_base10, BF16_MAX_EXPONENT_MAGNITUDE);
+    }
+}
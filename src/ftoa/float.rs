@@ -0,0 +1,273 @@
+//! Diy floating-point type and cached power-of-ten table used internally
+//! by the Grisu2 algorithm.
+//!
+//! `FloatType` mirrors `fpconv`'s `Fp`: a 64-bit fraction paired with a
+//! base-2 exponent, `frac * 2^exp`, sized so the arithmetic `grisu2.rs`
+//! performs on it stays exact within that 64-bit window.
+
+#![doc(hidden)]
+
+/// Number of bits held in `FloatType::frac`.
+const FRACT_SIZE: i32 = 64;
+
+/// A 64-bit fraction/exponent pair representing `frac * 2^exp`.
+#[derive(Copy, Clone)]
+pub(crate) struct FloatType {
+    pub frac: u64,
+    pub exp: i32,
+}
+
+impl FloatType {
+    /// Decompose an `f64`'s bit pattern into a `FloatType`, including the
+    /// implicit leading bit for normal values.
+    #[inline]
+    pub fn from_f64(d: f64) -> Self {
+        const EXPONENT_BIAS: i32 = 0x3FF + 52;
+        const DENORMAL_EXPONENT: i32 = -EXPONENT_BIAS + 1;
+        const FRACTION_MASK: u64 = 0x000F_FFFF_FFFF_FFFF;
+        const EXPONENT_MASK: u64 = 0x7FF0_0000_0000_0000;
+        const HIDDEN_BIT: u64 = 0x0010_0000_0000_0000;
+
+        let bits = d.to_bits();
+        let biased_e = ((bits & EXPONENT_MASK) >> 52) as i32;
+        let significand = bits & FRACTION_MASK;
+        if biased_e != 0 {
+            FloatType {
+                frac: significand + HIDDEN_BIT,
+                exp: biased_e - EXPONENT_BIAS,
+            }
+        } else {
+            FloatType {
+                frac: significand,
+                exp: DENORMAL_EXPONENT,
+            }
+        }
+    }
+
+    /// Normalize so the most-significant bit of `frac` is set.
+    #[inline]
+    pub fn normalize(&mut self) {
+        let shift = self.frac.leading_zeros() as i32;
+        self.frac <<= shift;
+        self.exp -= shift;
+    }
+
+    /// Multiply by another `FloatType`, keeping the upper 64 bits of the
+    /// full 128-bit product, rounded to nearest.
+    #[inline]
+    pub fn fast_multiply(&self, other: &Self) -> Self {
+        const MASK: u64 = 0xFFFF_FFFF;
+
+        let a = self.frac >> 32;
+        let b = self.frac & MASK;
+        let c = other.frac >> 32;
+        let d = other.frac & MASK;
+
+        let ac = a * c;
+        let bc = b * c;
+        let ad = a * d;
+        let bd = b * d;
+
+        let tmp = (bd >> 32) + (ad & MASK) + (bc & MASK) + (1 << 31);
+        FloatType {
+            frac: ac + (ad >> 32) + (bc >> 32) + (tmp >> 32),
+            exp: self.exp + other.exp + FRACT_SIZE,
+        }
+    }
+
+    /// Compute the normalized `(lower, upper)` boundaries halfway to the
+    /// adjacent representable values of the *source* type described by
+    /// `mantissa_bits`/`exponent_bits` (52/11 for `f64`, 23/8 for `f32`,
+    /// 10/5 for `f16`, 7/8 for `bf16`).
+    ///
+    /// `self` must be the un-normalized `FloatType` `from_f64` produced for
+    /// a value that is exactly representable in that source type (e.g. an
+    /// `f16` widened losslessly to `f64`), so the boundaries reflect that
+    /// type's ULP rather than `f64`'s.
+    #[inline]
+    pub fn normalized_boundaries_for(&self, mantissa_bits: u32, exponent_bits: u32) -> (Self, Self) {
+        // Re-express `self` in units of the source type's ULP: `mantissa_bits`
+        // below the leading bit for normal values, and the fixed subnormal
+        // ULP (`2^denormal_exponent`) below that type's normal range.
+        let bias = (1i32 << (exponent_bits - 1)) - 1;
+        let denormal_exponent = (1 - bias) - mantissa_bits as i32;
+        let shift = (52 - mantissa_bits as i32).max(denormal_exponent - self.exp);
+        debug_assert_eq!(
+            self.frac & ((1u64 << shift) - 1),
+            0,
+            "value must be exactly representable in the narrower source type",
+        );
+        let source = FloatType {
+            frac: self.frac >> shift,
+            exp: self.exp + shift,
+        };
+
+        let mut upper = FloatType {
+            frac: (source.frac << 1) + 1,
+            exp: source.exp - 1,
+        };
+        upper.normalize();
+
+        // The lower boundary is twice as close when `self` is an exact
+        // power of two in the source type (its mantissa is zero) and isn't
+        // that type's smallest normal value.
+        let is_lower_boundary_closer =
+            source.frac == 1u64 << mantissa_bits && source.exp != denormal_exponent;
+
+        let mut lower = if is_lower_boundary_closer {
+            FloatType {
+                frac: (source.frac << 2) - 1,
+                exp: source.exp - 2,
+            }
+        } else {
+            FloatType {
+                frac: (source.frac << 1) - 1,
+                exp: source.exp - 1,
+            }
+        };
+        lower.frac <<= lower.exp - upper.exp;
+        lower.exp = upper.exp;
+
+        (lower, upper)
+    }
+
+    /// `normalized_boundaries_for` at `f64`'s own precision.
+    #[inline]
+    pub fn normalized_boundaries(&self) -> (Self, Self) {
+        self.normalized_boundaries_for(52, 11)
+    }
+}
+
+// CACHED POWERS
+// -------------
+//
+// Correctly-rounded (to nearest, 64-bit) significands for `10^k`, for `k`
+// from -348 to 340 in steps of 8, alongside the binary exponent such that
+// `10^k ~= significand * 2^exponent`. Grisu2 looks these up rather than
+// approximating a power of ten with `f64` arithmetic, which would reinject
+// the rounding error the algorithm is designed to avoid.
+const CACHED_POWERS: [(u64, i16); 87] = [
+    (0xfa8f_d5a0_081c_0288, -1220),
+    (0xbaae_e17f_a23e_bf76, -1193),
+    (0x8b16_fb20_3055_ac76, -1166),
+    (0xcf42_894a_5dce_35ea, -1140),
+    (0x9a6b_b0aa_5565_3b2d, -1113),
+    (0xe61a_cf03_3d1a_45df, -1087),
+    (0xab70_fe17_c79a_c6ca, -1060),
+    (0xff77_b1fc_bebc_dc4f, -1034),
+    (0xbe56_91ef_416b_d60c, -1007),
+    (0x8dd0_1fad_907f_fc3c, -980),
+    (0xd351_5c28_3155_9a83, -954),
+    (0x9d71_ac8f_ada6_c9b5, -927),
+    (0xea9c_2277_23ee_8bcb, -901),
+    (0xaecc_4991_4078_536d, -874),
+    (0x823c_1279_5db6_ce57, -847),
+    (0xc210_9436_4dfb_5637, -821),
+    (0x9096_ea6f_3848_984f, -794),
+    (0xd774_85cb_2582_3ac7, -768),
+    (0xa086_cfcd_97bf_97f4, -741),
+    (0xef34_0a98_172a_ace5, -715),
+    (0xb238_67fb_2a35_b28e, -688),
+    (0x84c8_d4df_d2c6_3f3b, -661),
+    (0xc5dd_4427_1ad3_cdba, -635),
+    (0x936b_9fce_bb25_c996, -608),
+    (0xdbac_6c24_7d62_a584, -582),
+    (0xa3ab_6658_0d5f_daf6, -555),
+    (0xf3e2_f893_dec3_f126, -529),
+    (0xb5b5_ada8_aaff_80b8, -502),
+    (0x8762_5f05_6c7c_4a8b, -475),
+    (0xc9bc_ff60_34c1_3053, -449),
+    (0x964e_858c_91ba_2655, -422),
+    (0xdff9_7724_7029_7ebd, -396),
+    (0xa6df_bd9f_b8e5_b88f, -369),
+    (0xf8a9_5fcf_8874_7d94, -343),
+    (0xb944_7093_8fa8_9bcf, -316),
+    (0x8a08_f0f8_bf0f_156b, -289),
+    (0xcdb0_2555_6531_31b6, -263),
+    (0x993f_e2c6_d07b_7fac, -236),
+    (0xe45c_10c4_2a2b_3b06, -210),
+    (0xaa24_2499_6973_92d3, -183),
+    (0xfd87_b5f2_8300_ca0e, -157),
+    (0xbce5_0864_9211_1aeb, -130),
+    (0x8cbc_cc09_6f50_88cc, -103),
+    (0xd1b7_1758_e219_652c, -77),
+    (0x9c40_0000_0000_0000, -50),
+    (0xe8d4_a510_0000_0000, -24),
+    (0xad78_ebc5_ac62_0000, 3),
+    (0x813f_3978_f894_0984, 30),
+    (0xc097_ce7b_c907_15b3, 56),
+    (0x8f7e_32ce_7bea_5c70, 83),
+    (0xd5d2_38a4_abe9_8068, 109),
+    (0x9f4f_2726_179a_2245, 136),
+    (0xed63_a231_d4c4_fb27, 162),
+    (0xb0de_6538_8cc8_ada8, 189),
+    (0x83c7_088e_1aab_65db, 216),
+    (0xc45d_1df9_4271_1d9a, 242),
+    (0x924d_692c_a61b_e758, 269),
+    (0xda01_ee64_1a70_8dea, 295),
+    (0xa26d_a399_9aef_774a, 322),
+    (0xf209_787b_b47d_6b85, 348),
+    (0xb454_e4a1_79dd_1877, 375),
+    (0x865b_8692_5b9b_c5c2, 402),
+    (0xc835_53c5_c896_5d3d, 428),
+    (0x952a_b45c_fa97_a0b3, 455),
+    (0xde46_9fbd_99a0_5fe3, 481),
+    (0xa59b_c234_db39_8c25, 508),
+    (0xf6c6_9a72_a398_9f5c, 534),
+    (0xb7dc_bf53_54e9_bece, 561),
+    (0x88fc_f317_f222_41e2, 588),
+    (0xcc20_ce9b_d35c_78a5, 614),
+    (0x9816_5af3_7b21_53df, 641),
+    (0xe2a0_b5dc_971f_303a, 667),
+    (0xa8d9_d153_5ce3_b396, 694),
+    (0xfb9b_7cd9_a4a7_443c, 720),
+    (0xbb76_4c4c_a7a4_4410, 747),
+    (0x8bab_8eef_b640_9c1a, 774),
+    (0xd01f_ef10_a657_842c, 800),
+    (0x9b10_a4e5_e991_3129, 827),
+    (0xe710_9bfb_a19c_0c9d, 853),
+    (0xac28_20d9_623b_f429, 880),
+    (0x8044_4b5e_7aa7_cf85, 907),
+    (0xbf21_e440_03ac_dd2d, 933),
+    (0x8e67_9c2f_5e44_ff8f, 960),
+    (0xd433_179d_9c8c_b841, 986),
+    (0x9e19_db92_b4e3_1ba9, 1013),
+    (0xeb96_bf6e_badf_77d9, 1039),
+    (0xaf87_023b_9bf0_ee6b, 1066),
+];
+
+/// Decimal exponent of `CACHED_POWERS[0]`.
+const CACHED_POWERS_MIN_DEC_EXP: i32 = -348;
+
+/// `1 / log2(10)`, used to estimate which cache entry covers a given binary
+/// exponent.
+const D_1_LOG2_10: f64 = 0.301_029_995_663_981_2;
+
+/// Look up the cached power of ten whose combined exponent with `exp`
+/// places the product's binary exponent in Grisu2's working range, writing
+/// its decimal exponent to `*k`.
+///
+/// # Safety
+///
+/// Safe as long as `k` is a valid, aligned, writable `i32` pointer.
+#[inline]
+pub(crate) unsafe extern "C" fn cached_grisu_power(exp: i32, k: *mut i32) -> FloatType {
+    let dk = (-61 - exp) as f64 * D_1_LOG2_10 + 347.0;
+    let mut k_int = dk as i32;
+    if dk > k_int as f64 {
+        k_int += 1;
+    }
+
+    let index = ((k_int >> 3) + 1) as usize;
+    let dec_exp = CACHED_POWERS_MIN_DEC_EXP + ((index as i32) << 3);
+    // SAFETY: caller guarantees `k` is a valid, writable `i32` pointer.
+    unsafe {
+        *k = -dec_exp;
+    }
+
+    let (frac, bin_exp) = CACHED_POWERS[index];
+    FloatType {
+        frac,
+        exp: bin_exp as i32,
+    }
+}
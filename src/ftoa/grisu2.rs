@@ -67,6 +67,7 @@ use sealed::mem;
 use sealed::ptr;
 
 use super::float::{cached_grisu_power, FloatType};
+use super::options::{Options, DEFAULT_NEGATIVE_EXPONENT_BREAK, DEFAULT_POSITIVE_EXPONENT_BREAK};
 use super::util::*;
 
 // FTOA BASE10
@@ -83,6 +84,27 @@ const TENS: [u64; 20] = [
     10, 1
 ];
 
+/// Upper bound on the digit buffer, large enough to hold the shortest
+/// round-trip representation plus padding requested via
+/// `Options::min_significant_digits`, which is clamped to this size.
+pub(crate) const DIGITS_BUFFER_SIZE: usize = 256;
+
+// Mantissa/exponent bit widths for each source float type, used to compute
+// the correct rounding ULP when the value has been widened to `f64`.
+const F64_MANTISSA_BITS: u32 = 52;
+const F64_EXPONENT_BITS: u32 = 11;
+const F32_MANTISSA_BITS: u32 = 23;
+const F32_EXPONENT_BITS: u32 = 8;
+
+// Largest decimal exponent magnitude (i.e. number of leading or trailing
+// zeros `emit_digits` may need to write) for each source type's full finite
+// range, down to its smallest subnormal. Used to bound the buffer
+// `no_exponential`/a wide `positive_exponent_break`/`negative_exponent_break`
+// can force fixed-point notation to expand to, regardless of
+// `max_significant_digits`.
+pub(crate) const F64_MAX_EXPONENT_MAGNITUDE: i32 = 324;
+pub(crate) const F32_MAX_EXPONENT_MAGNITUDE: i32 = 45;
+
 // FPCONV GRISU
 
 /// Round digit to sane approximation.
@@ -166,11 +188,17 @@ fn generate_digits(fp: &FloatType, upper: &FloatType, lower: &FloatType, digits:
 }
 
 /// Core Grisu2 algorithm for the float formatter.
-unsafe extern "C" fn grisu2(d: f64, digits: *mut u8, k: *mut i32) -> i32
+///
+/// `mantissa_bits`/`exponent_bits` describe the bit layout of the
+/// originating float type (`f64` is 52/11). This lets `d` be a value
+/// widened from a narrower type (`f32`, `f16`, `bf16`) while still
+/// generating the shortest digit sequence that round-trips back to that
+/// narrower type, rather than to `f64`.
+unsafe extern "C" fn grisu2(d: f64, digits: *mut u8, k: *mut i32, mantissa_bits: u32, exponent_bits: u32) -> i32
 {
     let mut w = FloatType::from_f64(d);
 
-    let (mut lower, mut upper) = w.normalized_boundaries();
+    let (mut lower, mut upper) = w.normalized_boundaries_for(mantissa_bits, exponent_bits);
     w.normalize();
 
     let mut ki: i32 = mem::uninitialized();
@@ -188,28 +216,123 @@ unsafe extern "C" fn grisu2(d: f64, digits: *mut u8, k: *mut i32) -> i32
     return generate_digits(&w, &upper, &lower, digits, k);
 }
 
+/// Round the digit buffer down to at most `max` significant digits, using
+/// round-half-to-even on the truncated tail.
+///
+/// Dropping `ndigits - max` trailing digits raises the place value of the
+/// retained digits, so `k` is bumped by that amount to keep the represented
+/// value (`digits * 10^k`) unchanged (plus a further `+1` if rounding
+/// carries out of the most significant digit).
+///
+/// # Safety
+///
+/// Safe if `digits` is valid for `ndigits` elements and `0 < max < ndigits`.
+unsafe extern "C" fn round_significant_digits(digits: *mut u8, ndigits: i32, max: i32, k: *mut i32)
+    -> i32
+{
+    *k += ndigits - max;
+
+    let next = *digits.offset(max as isize) - b'0';
+    let round_up = if next != 5 {
+        next > 5
+    } else {
+        // Next digit is exactly 5: round to even, unless a nonzero tail
+        // beyond it makes the truncated value strictly greater.
+        let has_tail = (max + 1..ndigits).any(|i| *digits.offset(i as isize) != b'0');
+        if has_tail {
+            true
+        } else {
+            let prev = if max == 0 { 0 } else { *digits.offset(max as isize - 1) - b'0' };
+            prev % 2 == 1
+        }
+    };
+
+    if !round_up {
+        return max;
+    }
+
+    let mut i = max - 1;
+    while i >= 0 {
+        let digit = *digits.offset(i as isize) - b'0';
+        if digit == 9 {
+            *digits.offset(i as isize) = b'0';
+            i -= 1;
+        } else {
+            *digits.offset(i as isize) = digit + 1 + b'0';
+            return max;
+        }
+    }
+
+    // Carried out of the most significant digit: the value becomes a `1`
+    // followed by zeros, with the decimal point shifted one place right.
+    *digits = b'1';
+    ptr::write_bytes(digits.add(1), b'0', (max - 1) as usize);
+    *k += 1;
+
+    max
+}
+
+/// Zero-pad the digit buffer up to `min` significant digits.
+///
+/// The padding digits are appended on the least-significant side, so `k` is
+/// decremented by the pad count to keep the represented value (`digits *
+/// 10^k`) unchanged.
+///
+/// # Safety
+///
+/// Safe if `digits` is valid for at least `min` elements and `ndigits < min`.
+unsafe extern "C" fn pad_significant_digits(digits: *mut u8, ndigits: i32, min: i32, k: *mut i32) -> i32
+{
+    let pad = min - ndigits;
+    ptr::write_bytes(digits.offset(ndigits as isize), b'0', pad as usize);
+    *k -= pad;
+    min
+}
+
 /// Write the produced digits to string.
 ///
 /// Adds formatting for exponents, and other types of information.
-unsafe extern "C" fn emit_digits(digits: *mut u8, mut ndigits: i32, dest: *mut u8, k: i32)
+unsafe extern "C" fn emit_digits(digits: *mut u8, mut ndigits: i32, dest: *mut u8, k: i32, options: &Options)
     -> i32
 {
-    let exp = k + ndigits - 1;
-    let mut exp = absv!(exp);
+    let decimal_exp = k + ndigits - 1;
+    let mut exp = absv!(decimal_exp);
+
+    let exponential = if options.force_exponential {
+        true
+    } else if options.no_exponential {
+        false
+    } else if options.positive_exponent_break == DEFAULT_POSITIVE_EXPONENT_BREAK
+        && options.negative_exponent_break == DEFAULT_NEGATIVE_EXPONENT_BREAK
+    {
+        // As long as both breaks are untouched, reproduce the original
+        // hardcoded cutover exactly (`k >= 0 && exp < ndigits + 7`, else
+        // `k < 0 && (k > -7 || exp < 4)`) rather than the flat comparison
+        // below, so callers who never touch these options see no change in
+        // default output. `positive`/`negative_exponent_break` only take
+        // effect as plain `decimal_exp` thresholds once set to something
+        // other than their defaults.
+        !((k >= 0 && exp < ndigits + 7) || (k < 0 && (k > -7 || exp < 4)))
+    } else {
+        decimal_exp < options.negative_exponent_break || decimal_exp > options.positive_exponent_break
+    };
 
-    // write plain integer (with ".0" suffix).
-    if k >= 0 && exp < (ndigits + 7) {
+    // write plain integer (with optional ".0" suffix).
+    if !exponential && k >= 0 {
         let idx = ndigits as usize;
         let count = k as usize;
         ptr::copy_nonoverlapping(digits, dest, idx);
         ptr::write_bytes(dest.add(idx), b'0', count);
+        if options.trim_floats {
+            return ndigits + k;
+        }
         ptr::copy_nonoverlapping(b".0".as_ptr(), dest.add(idx + count), 2);
 
         return ndigits + k + 2;
     }
 
     // write decimal w/o scientific notation
-    if k < 0 && (k > -7 || exp < 4) {
+    if !exponential && k < 0 {
         let mut offset = ndigits - absv!(k);
         // fp < 1.0 -> write leading zero
         if offset <= 0 {
@@ -236,8 +359,12 @@ unsafe extern "C" fn emit_digits(digits: *mut u8, mut ndigits: i32, dest: *mut u
     }
 
     // write decimal w/ scientific notation
-    ndigits = minv!(ndigits, 18);
-
+    //
+    // `ndigits` is already bounded by `DIGITS_BUFFER_SIZE` (the digit buffer
+    // it was generated into), so no further clamp is needed here -- doing
+    // so used to silently truncate `max`/`min_significant_digits` results
+    // beyond 18 digits whenever the value happened to format in scientific
+    // notation.
     let mut idx: isize = 0;
     *dest.offset(idx) = *digits;
     idx += 1;
@@ -286,12 +413,61 @@ unsafe extern "C" fn emit_digits(digits: *mut u8, mut ndigits: i32, dest: *mut u
     idx as i32
 }
 
-unsafe extern "C" fn fpconv_dtoa(d: f64, dest: *mut u8) -> i32
+unsafe extern "C" fn fpconv_dtoa(
+    d: f64,
+    dest: *mut u8,
+    options: &Options,
+    mantissa_bits: u32,
+    exponent_bits: u32,
+) -> i32
 {
-    let mut digits: [u8; 18] = mem::uninitialized();
+    let mut digits: [u8; DIGITS_BUFFER_SIZE] = mem::uninitialized();
     let mut k: i32 = 0;
-    let ndigits = grisu2(d, digits.as_mut_ptr(), &mut k);
-    emit_digits(digits.as_mut_ptr(), ndigits, dest, k)
+    let mut ndigits = grisu2(d, digits.as_mut_ptr(), &mut k, mantissa_bits, exponent_bits);
+
+    // Clamp to the buffer capacity before casting to `i32`: an unclamped
+    // `NonZeroUsize` could both overflow `digits` when padding and, once
+    // truncated by an unchecked `as i32`, produce a negative `max` that
+    // violates `round_significant_digits`'s `0 < max < ndigits` precondition.
+    if let Some(max) = options.max_significant_digits {
+        let max = (max.get().min(DIGITS_BUFFER_SIZE) as i32).max(1);
+        if ndigits > max {
+            ndigits = round_significant_digits(digits.as_mut_ptr(), ndigits, max, &mut k);
+        }
+    }
+    if let Some(min) = options.min_significant_digits {
+        let min = (min.get().min(DIGITS_BUFFER_SIZE) as i32).max(1);
+        if ndigits < min {
+            ndigits = pad_significant_digits(digits.as_mut_ptr(), ndigits, min, &mut k);
+        }
+    }
+
+    emit_digits(digits.as_mut_ptr(), ndigits, dest, k, options)
+}
+
+/// Upper bound on the number of bytes `fpconv_dtoa`/`emit_digits` can write
+/// for the given `options`, for a source type whose fixed-point
+/// representation never needs more than `max_exponent_magnitude` leading or
+/// trailing zeros (see `F64_MAX_EXPONENT_MAGNITUDE` and friends).
+///
+/// Accounts for `min_significant_digits` padding the digit count up to
+/// `DIGITS_BUFFER_SIZE`, and for `no_exponential`/a wide
+/// `positive_exponent_break`/`negative_exponent_break` forcing fixed-point
+/// notation to expand by up to `max_exponent_magnitude` zeros instead of
+/// switching to scientific notation. `+ 9` covers the up to 7 trailing zeros
+/// plus `".0"` the default cutover writes in fixed-point notation, which is
+/// more than the decimal point and exponent marker/sign/digits scientific
+/// notation adds.
+#[inline]
+pub(crate) fn required_buffer_size(options: &Options, max_exponent_magnitude: i32) -> usize {
+    let min_digits = options.min_significant_digits.map_or(0, |min| min.get().min(DIGITS_BUFFER_SIZE));
+    let fixed_point_forced = options.no_exponential
+        || (!options.force_exponential
+            && (options.positive_exponent_break != DEFAULT_POSITIVE_EXPONENT_BREAK
+                || options.negative_exponent_break != DEFAULT_NEGATIVE_EXPONENT_BREAK));
+    let exponent_zeros = if fixed_point_forced { max_exponent_magnitude as usize } else { 0 };
+
+    DIGITS_BUFFER_SIZE.max(min_digits) + exponent_zeros + 9
 }
 
 // F32
@@ -301,10 +477,10 @@ unsafe extern "C" fn fpconv_dtoa(d: f64, dest: *mut u8) -> i32
 /// `f` must be non-special (NaN or infinite), non-negative,
 /// and non-zero.
 #[inline(always)]
-pub(crate) unsafe extern "C" fn float_base10(f: f32, first: *mut u8)
+pub(crate) unsafe extern "C" fn float_base10(f: f32, first: *mut u8, options: &Options)
     -> *mut u8
 {
-    double_base10(f as f64, first)
+    double_base10_for(f as f64, first, options, F32_MANTISSA_BITS, F32_EXPONENT_BITS)
 }
 
 // F64
@@ -314,9 +490,243 @@ pub(crate) unsafe extern "C" fn float_base10(f: f32, first: *mut u8)
 /// `d` must be non-special (NaN or infinite), non-negative,
 /// and non-zero.
 #[inline(always)]
-pub(crate) unsafe extern "C" fn double_base10(d: f64, first: *mut u8)
+pub(crate) unsafe extern "C" fn double_base10(d: f64, first: *mut u8, options: &Options)
     -> *mut u8
 {
-    let len = fpconv_dtoa(d, first);
+    double_base10_for(d, first, options, F64_MANTISSA_BITS, F64_EXPONENT_BITS)
+}
+
+/// `double_base10`, but for a value widened from a narrower source type.
+///
+/// `d` must be non-special (NaN or infinite), non-negative, and non-zero.
+#[inline(always)]
+unsafe extern "C" fn double_base10_for(
+    d: f64,
+    first: *mut u8,
+    options: &Options,
+    mantissa_bits: u32,
+    exponent_bits: u32,
+) -> *mut u8
+{
+    let len = fpconv_dtoa(d, first, options, mantissa_bits, exponent_bits);
     first.offset(len as isize)
 }
+
+// F16 / BF16
+
+cfg_if! {
+    if #[cfg(feature = "f16")] {
+
+use half::{bf16, f16};
+
+// Mantissa/exponent bit widths for the half-precision types.
+const F16_MANTISSA_BITS: u32 = 10;
+const F16_EXPONENT_BITS: u32 = 5;
+const BF16_MANTISSA_BITS: u32 = 7;
+const BF16_EXPONENT_BITS: u32 = 8;
+
+// See `F64_MAX_EXPONENT_MAGNITUDE`. `f16`'s smallest subnormal is 2^-24
+// (~6e-8) and its largest finite value is 65504; `bf16` shares `f32`'s 8-bit
+// exponent field, so its smallest subnormal is 2^-133 (~9.2e-41).
+pub(crate) const F16_MAX_EXPONENT_MAGNITUDE: i32 = 8;
+pub(crate) const BF16_MAX_EXPONENT_MAGNITUDE: i32 = 41;
+
+/// Forward to double_base10, widening losslessly to `f64` but generating
+/// the shortest digits that round-trip back to `f16`.
+///
+/// `f` must be non-special (NaN or infinite), non-negative, and non-zero.
+#[inline(always)]
+pub(crate) unsafe extern "C" fn f16_base10(f: f16, first: *mut u8, options: &Options)
+    -> *mut u8
+{
+    double_base10_for(f64::from(f), first, options, F16_MANTISSA_BITS, F16_EXPONENT_BITS)
+}
+
+/// Forward to double_base10, widening losslessly to `f64` but generating
+/// the shortest digits that round-trip back to `bf16`.
+///
+/// `f` must be non-special (NaN or infinite), non-negative, and non-zero.
+#[inline(always)]
+pub(crate) unsafe extern "C" fn bf16_base10(f: bf16, first: *mut u8, options: &Options)
+    -> *mut u8
+{
+    double_base10_for(f64::from(f), first, options, BF16_MANTISSA_BITS, BF16_EXPONENT_BITS)
+}
+
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::num::NonZeroUsize;
+    use core::str;
+
+    /// Large enough to hold any formatted value in these tests, including
+    /// ones padded out to `DIGITS_BUFFER_SIZE` significant digits.
+    const TEST_BUFFER_SIZE: usize = DIGITS_BUFFER_SIZE + 16;
+
+    /// Format `d` with `options` into `buffer`, returning the written `str`.
+    fn format<'a>(d: f64, options: &Options, buffer: &'a mut [u8; TEST_BUFFER_SIZE]) -> &'a str {
+        // SAFETY: `buffer` is far larger than any `f64`'s formatted size.
+        unsafe {
+            let first = buffer.as_mut_ptr();
+            let last = double_base10(d, first, options);
+            let len = last.offset_from(first) as usize;
+            str::from_utf8(&buffer[..len]).unwrap()
+        }
+    }
+
+    #[test]
+    fn min_significant_digits_preserves_value() {
+        // Regression test: padding used to leave `k` unadjusted, scaling
+        // 1.5 up to 150.0 instead of zero-padding it to 1.500.
+        let mut options = Options::new();
+        options.set_min_significant_digits(NonZeroUsize::new(4));
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        assert_eq!(format(1.5, &options, &mut buffer), "1.500");
+    }
+
+    #[test]
+    fn min_significant_digits_pads_scientific_notation_in_full() {
+        // Regression test: emit_digits used to clamp to 18 digits in its
+        // scientific-notation branch, silently truncating a larger
+        // min_significant_digits request for any value that goes
+        // scientific (1e20 does, under the default exponent cutover).
+        let mut options = Options::new();
+        options.set_min_significant_digits(NonZeroUsize::new(30));
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        let expected = std::format!("1.{}e+20", "0".repeat(29));
+        assert_eq!(format(1e20, &options, &mut buffer), expected);
+    }
+
+    #[test]
+    fn min_significant_digits_is_clamped_to_buffer_capacity() {
+        // Regression test: an oversized min_significant_digits used to
+        // overflow the fixed-size digits buffer.
+        let mut options = Options::new();
+        options.set_min_significant_digits(NonZeroUsize::new(1_000_000));
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        // 256 significant digits, plus the decimal point.
+        assert_eq!(format(1.5, &options, &mut buffer).len(), DIGITS_BUFFER_SIZE + 1);
+    }
+
+    #[test]
+    fn max_significant_digits_rounds_half_to_even() {
+        let mut options = Options::new();
+        options.set_max_significant_digits(NonZeroUsize::new(2));
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        assert_eq!(format(1.25, &options, &mut buffer), "1.2");
+        assert_eq!(format(1.35, &options, &mut buffer), "1.4");
+    }
+
+    #[test]
+    fn max_significant_digits_carries_out_of_most_significant_digit() {
+        let mut options = Options::new();
+        options.set_max_significant_digits(NonZeroUsize::new(1));
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        assert_eq!(format(9.5, &options, &mut buffer), "10.0");
+    }
+
+    #[test]
+    fn default_exponent_cutover_matches_legacy_behavior() {
+        // Regression test: a flat `decimal_exp` comparison against the new
+        // positive/negative_exponent_break options used to change these
+        // defaults from the original hardcoded cutover.
+        let options = Options::new();
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        assert_eq!(format(1e11, &options, &mut buffer), "1e+11");
+        assert_eq!(format(0.00001234, &options, &mut buffer), "1.234e-5");
+    }
+
+    #[test]
+    fn force_exponential_overrides_default_cutover() {
+        let mut options = Options::new();
+        options.set_force_exponential(true);
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        assert_eq!(format(123.0, &options, &mut buffer), "1.23e+2");
+    }
+
+    #[test]
+    fn no_exponential_forces_full_fixed_point_expansion() {
+        // The original chunk0-2 request: a very large value must emit the
+        // full run of digits instead of switching to scientific notation.
+        let mut options = Options::new();
+        options.set_no_exponential(true);
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        let expected = std::format!("1{}.0", "0".repeat(30));
+        assert_eq!(format(1e30, &options, &mut buffer), expected);
+    }
+
+    #[test]
+    fn positive_exponent_break_widens_fixed_point_range() {
+        let mut options = Options::new();
+        options.set_positive_exponent_break(50);
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        assert_eq!(format(1e11, &options, &mut buffer), "100000000000.0");
+    }
+
+    #[test]
+    fn negative_exponent_break_widens_fixed_point_range() {
+        let mut options = Options::new();
+        options.set_negative_exponent_break(-20);
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        assert_eq!(format(1e-10, &options, &mut buffer), "0.0000000001");
+    }
+
+    #[cfg(feature = "f16")]
+    fn format_f16<'a>(f: f16, buffer: &'a mut [u8; TEST_BUFFER_SIZE]) -> &'a str {
+        // SAFETY: `buffer` is far larger than any `f16`'s formatted size.
+        unsafe {
+            let first = buffer.as_mut_ptr();
+            let last = f16_base10(f, first, &Options::new());
+            let len = last.offset_from(first) as usize;
+            str::from_utf8(&buffer[..len]).unwrap()
+        }
+    }
+
+    #[cfg(feature = "f16")]
+    fn format_bf16<'a>(f: bf16, buffer: &'a mut [u8; TEST_BUFFER_SIZE]) -> &'a str {
+        // SAFETY: `buffer` is far larger than any `bf16`'s formatted size.
+        unsafe {
+            let first = buffer.as_mut_ptr();
+            let last = bf16_base10(f, first, &Options::new());
+            let len = last.offset_from(first) as usize;
+            str::from_utf8(&buffer[..len]).unwrap()
+        }
+    }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn f16_base10_round_trips_power_of_two_boundary() {
+        // 2.0 is an exact power of two in f16 (zero mantissa), which makes
+        // its lower ULP boundary twice as close as usual -- the
+        // `is_lower_boundary_closer` branch of `normalized_boundaries_for`.
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        let value = f16::from_f64(2.0);
+        let formatted = format_f16(value, &mut buffer);
+        assert_eq!(f16::from_f64(formatted.parse::<f64>().unwrap()), value);
+    }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn f16_base10_round_trips_smallest_denormal_boundary() {
+        // The smallest denormal widens to an `f64` power of two, but its
+        // lower neighbor in `f16` (zero) is a full ULP away, so
+        // `normalized_boundaries_for` must not treat it as the
+        // boundary-doubling case.
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        let value = f16::from_bits(1);
+        let formatted = format_f16(value, &mut buffer);
+        assert_eq!(f16::from_f64(formatted.parse::<f64>().unwrap()), value);
+    }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn bf16_base10_round_trips_normal_value() {
+        let mut buffer = [0u8; TEST_BUFFER_SIZE];
+        let value = bf16::from_f64(1.0 / 3.0);
+        let formatted = format_bf16(value, &mut buffer);
+        assert_eq!(bf16::from_f64(formatted.parse::<f64>().unwrap()), value);
+    }
+}
@@ -3,6 +3,7 @@
 // Re-export the modules
 mod util;
 mod basen;
+mod options;
 
 cfg_if! {
     if #[cfg(feature = "grisu3")] {
@@ -21,3 +22,4 @@ mod api;
 // Re-exports
 pub(crate) use self::util::exponent_notation_char;
 pub use self::api::*;
+pub use self::options::Options;
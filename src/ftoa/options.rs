@@ -0,0 +1,180 @@
+//! Configuration options for the Grisu2 float-to-string formatter.
+
+use core::num::NonZeroUsize;
+
+/// Options to customize how floats are formatted to string.
+///
+/// These mirror the significant-digit and trimming controls of `printf`-style
+/// formatting, letting callers cap or pad the number of significant digits
+/// produced by the shortest round-trip [`grisu2`](super::grisu2) algorithm.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Maximum number of significant digits to write.
+    ///
+    /// Digits beyond this count are rounded half-to-even into the retained
+    /// digits. `None` leaves the shortest round-trip digit count untouched.
+    pub(crate) max_significant_digits: Option<NonZeroUsize>,
+    /// Minimum number of significant digits to write.
+    ///
+    /// Shorter results are zero-padded on the fractional side. `None` leaves
+    /// the shortest round-trip digit count untouched.
+    pub(crate) min_significant_digits: Option<NonZeroUsize>,
+    /// Don't write the trailing `".0"` for integral values.
+    pub(crate) trim_floats: bool,
+    /// Maximum decimal exponent to write in fixed-point notation.
+    ///
+    /// Values whose decimal exponent (`k + ndigits - 1`) is greater than
+    /// this are written in scientific notation, unless `no_exponential`
+    /// overrides this.
+    ///
+    /// Caveat: see [`Options::positive_exponent_break`]'s doc comment.
+    pub(crate) positive_exponent_break: i32,
+    /// Minimum decimal exponent to write in fixed-point notation.
+    ///
+    /// Values whose decimal exponent (`k + ndigits - 1`) is less than this
+    /// are written in scientific notation, unless `no_exponential`
+    /// overrides this.
+    ///
+    /// Caveat: see [`Options::positive_exponent_break`]'s doc comment.
+    pub(crate) negative_exponent_break: i32,
+    /// Always write scientific notation, regardless of magnitude.
+    pub(crate) force_exponential: bool,
+    /// Always write fixed-point notation, regardless of magnitude.
+    pub(crate) no_exponential: bool,
+}
+
+/// Default maximum decimal exponent before switching to scientific notation.
+///
+/// Only meaningful as a sentinel: as long as both exponent breaks are left
+/// at their defaults, `emit_digits` reproduces the original hardcoded
+/// cutover exactly rather than comparing against this value directly. See
+/// the comment on `emit_digits` in `grisu2.rs`.
+pub(crate) const DEFAULT_POSITIVE_EXPONENT_BREAK: i32 = 16;
+
+/// Default minimum decimal exponent before switching to scientific notation.
+///
+/// See [`DEFAULT_POSITIVE_EXPONENT_BREAK`].
+pub(crate) const DEFAULT_NEGATIVE_EXPONENT_BREAK: i32 = -5;
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            max_significant_digits: None,
+            min_significant_digits: None,
+            trim_floats: false,
+            positive_exponent_break: DEFAULT_POSITIVE_EXPONENT_BREAK,
+            negative_exponent_break: DEFAULT_NEGATIVE_EXPONENT_BREAK,
+            force_exponential: false,
+            no_exponential: false,
+        }
+    }
+}
+
+impl Options {
+    /// Create new options with default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the maximum number of significant digits to write.
+    #[inline]
+    pub fn max_significant_digits(&self) -> Option<NonZeroUsize> {
+        self.max_significant_digits
+    }
+
+    /// Set the maximum number of significant digits to write.
+    #[inline]
+    pub fn set_max_significant_digits(&mut self, max_significant_digits: Option<NonZeroUsize>) {
+        self.max_significant_digits = max_significant_digits;
+    }
+
+    /// Get the minimum number of significant digits to write.
+    #[inline]
+    pub fn min_significant_digits(&self) -> Option<NonZeroUsize> {
+        self.min_significant_digits
+    }
+
+    /// Set the minimum number of significant digits to write.
+    #[inline]
+    pub fn set_min_significant_digits(&mut self, min_significant_digits: Option<NonZeroUsize>) {
+        self.min_significant_digits = min_significant_digits;
+    }
+
+    /// Get whether to trim the trailing `".0"` for integral values.
+    #[inline]
+    pub fn trim_floats(&self) -> bool {
+        self.trim_floats
+    }
+
+    /// Set whether to trim the trailing `".0"` for integral values.
+    #[inline]
+    pub fn set_trim_floats(&mut self, trim_floats: bool) {
+        self.trim_floats = trim_floats;
+    }
+
+    /// Get the maximum decimal exponent before using scientific notation.
+    ///
+    /// Caveat: this only takes effect as a plain threshold on its own once
+    /// *either* this or [`negative_exponent_break`](Self::negative_exponent_break)
+    /// has been set away from its default. As long as both are left at their
+    /// defaults (`16`/`-5`), formatting instead reproduces the original,
+    /// magnitude-dependent cutover that predates these options -- which is
+    /// not exactly "cut over at decimal exponent 16". A caller who explicitly
+    /// sets this (and leaves the other at its default) to the same value the
+    /// default happens to have will still get the legacy behavior, not the
+    /// flat threshold.
+    #[inline]
+    pub fn positive_exponent_break(&self) -> i32 {
+        self.positive_exponent_break
+    }
+
+    /// Set the maximum decimal exponent before using scientific notation.
+    ///
+    /// See the caveat on [`positive_exponent_break`](Self::positive_exponent_break).
+    #[inline]
+    pub fn set_positive_exponent_break(&mut self, positive_exponent_break: i32) {
+        self.positive_exponent_break = positive_exponent_break;
+    }
+
+    /// Get the minimum decimal exponent before using scientific notation.
+    ///
+    /// See the caveat on [`positive_exponent_break`](Self::positive_exponent_break);
+    /// it applies symmetrically here.
+    #[inline]
+    pub fn negative_exponent_break(&self) -> i32 {
+        self.negative_exponent_break
+    }
+
+    /// Set the minimum decimal exponent before using scientific notation.
+    ///
+    /// See the caveat on [`positive_exponent_break`](Self::positive_exponent_break).
+    #[inline]
+    pub fn set_negative_exponent_break(&mut self, negative_exponent_break: i32) {
+        self.negative_exponent_break = negative_exponent_break;
+    }
+
+    /// Get whether to always write scientific notation.
+    #[inline]
+    pub fn force_exponential(&self) -> bool {
+        self.force_exponential
+    }
+
+    /// Set whether to always write scientific notation.
+    #[inline]
+    pub fn set_force_exponential(&mut self, force_exponential: bool) {
+        self.force_exponential = force_exponential;
+    }
+
+    /// Get whether to always write fixed-point notation.
+    #[inline]
+    pub fn no_exponential(&self) -> bool {
+        self.no_exponential
+    }
+
+    /// Set whether to always write fixed-point notation.
+    #[inline]
+    pub fn set_no_exponential(&mut self, no_exponential: bool) {
+        self.no_exponential = no_exponential;
+    }
+}